@@ -0,0 +1,188 @@
+//! Byte-String Quote/Unquote Operations
+//!
+//! This module mirrors the top-level [`quote()`](crate::quote) and [`unquote()`](crate::unquote)
+//! operations, but operates directly on byte-slices (`&[u8]`) rather than `&str`. This is useful
+//! whenever the data to be quoted is not guaranteed to be valid UTF-8, such as raw `argv` entries
+//! or `std::ffi::OsStr` values on unix-like systems (e.g. via `OsStrExt::as_bytes()`).
+//!
+//! The quoting and unquoting rules are identical to the top-level operations, just applied
+//! byte-by-byte instead of char-by-char.
+//!
+//! # Examples
+//!
+//! ```
+//! assert_eq!(r_shquote::bytes::quote(b"foobar"), b"'foobar'");
+//! assert_eq!(r_shquote::bytes::unquote(b"foo'bar'").unwrap(), b"foobar");
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::UnquoteError;
+
+/// Quote byte-string
+///
+/// This is the byte-slice equivalent of [`crate::quote()`]. See its documentation for details on
+/// the quoting rules applied.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(r_shquote::bytes::quote(b"foobar"), b"'foobar'");
+/// ```
+pub fn quote(source: &[u8]) -> Vec<u8> {
+    // See `crate::quote()` for rationale on why this purposefully produces overly verbose
+    // output in some cases (e.g. quoting a single-quote).
+
+    let mut acc = Vec::with_capacity(source.len() + 2);
+    let mut parts = source.split(|&b| b == b'\'');
+
+    acc.push(b'\'');
+
+    if let Some(part) = parts.next() {
+        acc.extend_from_slice(part);
+    }
+
+    parts.fold(&mut acc, |acc, part| {
+        acc.extend_from_slice(b"'\\''");
+        acc.extend_from_slice(part);
+        acc
+    });
+
+    acc.push(b'\'');
+    acc
+}
+
+fn unquote_open_single(acc: &mut Vec<u8>, cursor: &mut core::iter::Enumerate<core::slice::Iter<u8>>) -> bool {
+    // Byte-equivalent of `crate::unquote_open_single()`.
+    for (_, &b) in cursor {
+        match b {
+            b'\'' => return true,
+            _ => acc.push(b),
+        }
+    }
+
+    false
+}
+
+fn unquote_open_double(acc: &mut Vec<u8>, cursor: &mut core::iter::Enumerate<core::slice::Iter<u8>>) -> bool {
+    // Byte-equivalent of `crate::unquote_open_double()`.
+    loop {
+        match cursor.next() {
+            Some((_, &inner_ch)) if inner_ch == b'"' => {
+                return true;
+            },
+            Some((_, &inner_ch)) if inner_ch == b'\\' => {
+                match cursor.next() {
+                    Some((_, &esc_ch)) if esc_ch == b'"'  ||
+                                          esc_ch == b'\\' ||
+                                          esc_ch == b'`'  ||
+                                          esc_ch == b'$'  ||
+                                          esc_ch == b'\n' => {
+                        acc.push(esc_ch);
+                    },
+                    Some((_, &esc_ch)) => {
+                        acc.push(b'\\');
+                        acc.push(esc_ch);
+                    },
+                    None => {
+                        return false;
+                    },
+                }
+            },
+            Some((_, &inner_ch)) => {
+                acc.push(inner_ch);
+            },
+            None => {
+                return false;
+            },
+        }
+    }
+}
+
+fn unquote_open_escape(acc: &mut Vec<u8>, cursor: &mut core::iter::Enumerate<core::slice::Iter<u8>>) {
+    // Byte-equivalent of `crate::unquote_open_escape()`.
+    if let Some((_, &esc_ch)) = cursor.next() {
+        if esc_ch != b'\n' {
+            acc.push(esc_ch);
+        }
+    }
+}
+
+/// Unquote byte-string
+///
+/// This is the byte-slice equivalent of [`crate::unquote()`]. See its documentation for details
+/// on the unquoting rules applied.
+///
+/// Since there is no notion of a "character" in a raw byte-slice, the `char_cursor` field of the
+/// returned [`UnquoteError`] is always equal to its `byte_cursor` field.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(r_shquote::bytes::unquote(b"foobar").unwrap(), b"foobar");
+/// ```
+pub fn unquote(source: &[u8]) -> Result<Vec<u8>, UnquoteError> {
+    let mut acc = Vec::with_capacity(source.len());
+
+    let mut cursor = source.iter().enumerate();
+    loop {
+        match cursor.next() {
+            Some((next_pos, &next_ch)) if next_ch == b'\'' => {
+                if !unquote_open_single(&mut acc, &mut cursor) {
+                    break Err(
+                        UnquoteError::UnterminatedSingleQuote {
+                            char_cursor: next_pos,
+                            byte_cursor: next_pos,
+                        }
+                    );
+                }
+            },
+            Some((next_pos, &next_ch)) if next_ch == b'"' => {
+                if !unquote_open_double(&mut acc, &mut cursor) {
+                    break Err(
+                        UnquoteError::UnterminatedDoubleQuote {
+                            char_cursor: next_pos,
+                            byte_cursor: next_pos,
+                        }
+                    );
+                }
+            },
+            Some((_, &next_ch)) if next_ch == b'\\' => {
+                unquote_open_escape(&mut acc, &mut cursor);
+            },
+            Some((_, &next_ch)) => {
+                acc.push(next_ch);
+            },
+            None => {
+                break Ok(acc);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        assert_eq!(quote(b"foobar"), b"'foobar'");
+        assert_eq!(quote(b""), b"''");
+        assert_eq!(quote(b"'"), b"''\\'''");
+
+        assert_eq!(unquote(b"foobar").unwrap(), b"foobar");
+        assert_eq!(unquote(b"foo'bar'").unwrap(), b"foobar");
+        assert_eq!(unquote(b"foo\"bar\"").unwrap(), b"foobar");
+        assert_eq!(unquote(b"\\foobar\\").unwrap(), b"foobar");
+        assert_eq!(unquote(b"\\'foobar\\'").unwrap(), b"'foobar'");
+    }
+
+    #[test]
+    fn non_utf8() {
+        // Byte `0x80` is not a valid standalone UTF-8 sequence, but the byte-oriented API
+        // round-trips it just fine, unlike the `&str`-based API which cannot represent it.
+        assert_eq!(quote(b"\x80"), b"'\x80'");
+        assert_eq!(unquote(b"'\x80'").unwrap(), b"\x80");
+    }
+}
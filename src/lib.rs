@@ -36,6 +36,28 @@
 //!
 //! assert_eq!(str, r_shquote::unquote(&r_shquote::quote(str)).unwrap());
 //! ```
+//!
+//! # `no_std`
+//!
+//! This crate is `no_std`, but requires `alloc`. The `std` feature is enabled by default, and
+//! only adds the `std::error::Error` implementation for the error types. Disable default features
+//! to build without `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The single-character match arms below are written with an explicit `if c == '...'` guard
+// rather than a literal pattern, for symmetry with the neighboring multi-condition arms in the
+// same `match` (e.g. the escape-sequence handling in `unquote_open_double`).
+#![allow(clippy::redundant_guards)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, format, string::{String, ToString}, vec::Vec};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+pub mod bytes;
 
 /// Error information for unquote operations
 ///
@@ -68,12 +90,13 @@ pub enum UnquoteError {
     },
 }
 
-impl std::fmt::Display for UnquoteError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for UnquoteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for UnquoteError { }
 
 /// Quote string
@@ -122,7 +145,166 @@ pub fn quote(source: &str) -> String {
     acc
 }
 
-fn unquote_open_single(acc: &mut String, cursor: &mut std::iter::Enumerate<std::str::CharIndices>) -> bool {
+/// Error information for fallible quote operations
+///
+/// This error is returned by [`try_quote()`] when the input cannot be quoted safely. It contains
+/// the character and byte offsets of the cursor where the error originated, same as
+/// [`UnquoteError`].
+///
+/// # Examples
+///
+/// ```
+/// let res = r_shquote::try_quote("foo\x1bbar").unwrap_err();
+///
+/// match res {
+///     r_shquote::QuoteError::ControlCharacter { char_cursor: x, .. } => {
+///         println!("Control character at position {}", x);
+///     },
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub enum QuoteError {
+    ControlCharacter {
+        char_cursor: usize,
+        byte_cursor: usize,
+    },
+}
+
+impl core::fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for QuoteError { }
+
+fn is_control_character(c: char) -> bool {
+    // C0 controls (including DEL) and C1 controls. These are the characters that can trigger
+    // terminal-escape sequences or otherwise confuse an interactive shell if pasted verbatim,
+    // even though they are all perfectly valid to appear literally inside single-quotes as far
+    // as POSIX Shell quoting rules are concerned.
+    matches!(c as u32, 0x00..=0x1f | 0x7f..=0x9f)
+}
+
+/// Quote string, rejecting control characters
+///
+/// This behaves like [`quote()`], except it refuses to quote a string containing a C0 or C1
+/// control character (or DEL), returning [`QuoteError::ControlCharacter`] instead. A raw control
+/// character embedded in otherwise valid single-quoted output is still copied verbatim by a
+/// shell, which can trigger terminal-escape sequences or other mischief if the result is ever
+/// pasted into an interactive prompt, and makes the output hard to inspect.
+///
+/// Use this whenever the quoted output might be shown to, or pasted by, a human into an
+/// interactive shell. Use the infallible [`quote()`] when the caller knows the input cannot
+/// contain control characters, or the output is only ever fed to a non-interactive shell (e.g.
+/// `sh -c`).
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(r_shquote::try_quote("foobar").unwrap(), "'foobar'");
+/// assert!(r_shquote::try_quote("foo\x1bbar").is_err());
+/// ```
+pub fn try_quote(source: &str) -> Result<String, QuoteError> {
+    for (char_idx, (byte_idx, c)) in source.char_indices().enumerate() {
+        if is_control_character(c) {
+            return Err(
+                QuoteError::ControlCharacter {
+                    char_cursor: char_idx,
+                    byte_cursor: byte_idx,
+                }
+            );
+        }
+    }
+
+    Ok(quote(source))
+}
+
+/// Quote string using ANSI-C `$'...'` quoting
+///
+/// This is an alternative to [`try_quote()`] for strings containing control characters: instead
+/// of rejecting them, it quotes the string using the `$'...'` syntax supported by bash, zsh, and
+/// other POSIX-Shell-derived shells. Whenever the input contains a control character, the whole
+/// token is wrapped as `$'...'` and non-printable bytes are escaped (`\n`, `\t`, `\r`, `\a`,
+/// `\b`, `\f`, `\v`, `\\`, `\'`, or `\xHH` for anything else), while ordinary runs are copied
+/// literally. If the input contains no control characters, this falls back to the plain
+/// single-quote form produced by [`quote()`].
+///
+/// Note that `$'...'` is not understood by a strict POSIX `sh`, only by shells that implement
+/// this extension. Only use this when the target shell is known to support it.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(r_shquote::quote_ansi_c("foobar"), "'foobar'");
+/// assert_eq!(r_shquote::quote_ansi_c("foo\nbar"), "$'foo\\nbar'");
+/// ```
+pub fn quote_ansi_c(source: &str) -> String {
+    if !source.chars().any(is_control_character) {
+        return quote(source);
+    }
+
+    let mut acc = String::with_capacity(source.len() + 3);
+    acc.push_str("$'");
+
+    for c in source.chars() {
+        match c {
+            '\n' => acc.push_str("\\n"),
+            '\t' => acc.push_str("\\t"),
+            '\r' => acc.push_str("\\r"),
+            '\x07' => acc.push_str("\\a"),
+            '\x08' => acc.push_str("\\b"),
+            '\x0c' => acc.push_str("\\f"),
+            '\x0b' => acc.push_str("\\v"),
+            '\\' => acc.push_str("\\\\"),
+            '\'' => acc.push_str("\\'"),
+            c if is_control_character(c) => {
+                // `c` may be a multi-byte C1 control character (U+0080..=U+009F), so each of
+                // its UTF-8 bytes needs its own `\xHH` escape; hex-formatting the scalar value
+                // directly would silently truncate it to a single, incorrect byte.
+                let mut buf = [0u8; 4];
+                for byte in c.encode_utf8(&mut buf).as_bytes() {
+                    acc.push_str(&format!("\\x{:02x}", byte));
+                }
+            },
+            c => acc.push(c),
+        }
+    }
+
+    acc.push('\'');
+    acc
+}
+
+fn needs_quoting(c: char) -> bool {
+    !matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '@' | '%' | '_' | '-' | '+' | '=' | ':' | ',' | '.' | '/')
+}
+
+/// Quote string, avoiding allocation when possible
+///
+/// This behaves like [`quote()`], but returns a borrowed [`Cow::Borrowed`] instead of allocating
+/// a new string whenever the input is already safe to pass to a shell unquoted, i.e. it is
+/// non-empty and contains none of the shell-special characters. Otherwise, it falls back to
+/// [`quote()`] and returns the owned result.
+///
+/// This is a meaningful win for callers that quote large batches of tokens where most of them
+/// are already safe, such as filenames or identifiers.
+///
+/// # Examples
+///
+/// ```
+/// assert!(matches!(r_shquote::quote_cow("foobar"), std::borrow::Cow::Borrowed(_)));
+/// assert!(matches!(r_shquote::quote_cow("foo bar"), std::borrow::Cow::Owned(_)));
+/// ```
+pub fn quote_cow(source: &str) -> Cow<'_, str> {
+    if !source.is_empty() && !source.chars().any(needs_quoting) {
+        Cow::Borrowed(source)
+    } else {
+        Cow::Owned(quote(source))
+    }
+}
+
+fn unquote_open_single(acc: &mut String, cursor: &mut core::iter::Enumerate<core::str::CharIndices>) -> bool {
     // This decodes a single-quote sequence. The opening single-quote was already parsed by
     // the caller. Both `&source[start]` and `cursor` point to the first character following
     // the opening single-quote.
@@ -140,7 +322,7 @@ fn unquote_open_single(acc: &mut String, cursor: &mut std::iter::Enumerate<std::
     false
 }
 
-fn unquote_open_double(acc: &mut String, cursor: &mut std::iter::Enumerate<std::str::CharIndices>) -> bool {
+fn unquote_open_double(acc: &mut String, cursor: &mut core::iter::Enumerate<core::str::CharIndices>) -> bool {
     // This decodes a double-quote sequence. The opening double-quote was already parsed by
     // the caller. Both `&source[start]` and `cursor` point to the first character following
     // the opening double-quote.
@@ -193,7 +375,7 @@ fn unquote_open_double(acc: &mut String, cursor: &mut std::iter::Enumerate<std::
     }
 }
 
-fn unquote_open_escape(acc: &mut String, cursor: &mut std::iter::Enumerate<std::str::CharIndices>) {
+fn unquote_open_escape(acc: &mut String, cursor: &mut core::iter::Enumerate<core::str::CharIndices>) {
     // This decodes an escape sequence outside of any quote. The opening backslash was already
     // parsed by the caller. Both `&source[start]` and `cursor` point to the first character
     // following the opening backslash.
@@ -269,10 +451,212 @@ pub fn unquote(source: &str) -> Result<String, UnquoteError> {
     }
 }
 
+/// Unquote string, avoiding allocation when possible
+///
+/// This behaves like [`unquote()`], but returns a borrowed [`Cow::Borrowed`] instead of
+/// allocating a new string whenever the input contains none of `'`, `"`, or `\`, since in that
+/// case the input is already its own unquoted form. Otherwise, it falls back to [`unquote()`] and
+/// returns the owned result.
+///
+/// # Examples
+///
+/// ```
+/// assert!(matches!(r_shquote::unquote_cow("foobar").unwrap(), std::borrow::Cow::Borrowed(_)));
+/// assert!(matches!(r_shquote::unquote_cow("'foobar'").unwrap(), std::borrow::Cow::Owned(_)));
+/// ```
+pub fn unquote_cow(source: &str) -> Result<Cow<'_, str>, UnquoteError> {
+    if !source.contains(['\'', '"', '\\']) {
+        Ok(Cow::Borrowed(source))
+    } else {
+        unquote(source).map(Cow::Owned)
+    }
+}
+
+/// Options to customize [`split()`] behavior
+///
+/// By default, [`split()`] recognizes only space, tab, and newline as word delimiters, and does
+/// not treat `#` as starting a comment. `SplitOptions` allows callers to customize both of these,
+/// for instance to reuse the same splitter for config-file-like input where `#`-comments are
+/// expected.
+///
+/// # Examples
+///
+/// ```
+/// let opts = r_shquote::SplitOptions::new().comments(true);
+///
+/// assert_eq!(opts.split("foo # a comment\nbar").unwrap(), vec!["foo", "bar"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SplitOptions {
+    comments: bool,
+    delimiters: String,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        SplitOptions {
+            comments: false,
+            delimiters: " \t\n".to_string(),
+        }
+    }
+}
+
+impl SplitOptions {
+    /// Create a new set of options with the default behavior of [`split()`]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Toggle recognition of `#`-comments
+    ///
+    /// When enabled, a `#` appearing at the start of a word (i.e., at a delimiter, or at the
+    /// very start of the input) introduces a comment that extends to the next newline and
+    /// produces no token. A `#` appearing in the middle of an unquoted word remains literal.
+    pub fn comments(mut self, enable: bool) -> Self {
+        self.comments = enable;
+        self
+    }
+
+    /// Set the characters treated as word delimiters
+    ///
+    /// Defaults to space, tab, and newline.
+    pub fn delimiters(mut self, delimiters: &str) -> Self {
+        self.delimiters = delimiters.to_string();
+        self
+    }
+
+    /// Split a command line into its arguments, using these options
+    ///
+    /// See [`split()`] for the default behavior this customizes.
+    pub fn split(&self, source: &str) -> Result<Vec<String>, UnquoteError> {
+        // This walks the input exactly like `unquote()` does, reusing the same per-character
+        // quote/escape handling. The only addition is tracking whether we are currently inside a
+        // word (i.e., not at a delimiter), so we know when to flush the accumulator as a new
+        // argument. Opening a quote or escape sequence always counts as starting a word, even if
+        // it is empty (e.g. `''`), which is why `in_word` is set before the quote/escape is
+        // parsed.
+        //
+        // A `#` is only treated as starting a comment while still at a delimiter (i.e. not
+        // `in_word`), matching POSIX shell behavior. The comment, including its leading `#`,
+        // produces no token and is discarded up to (and including) the next newline, or EOF.
+        let mut words = Vec::new();
+        let mut acc = String::new();
+        let mut in_word = false;
+
+        let mut cursor = source.char_indices().enumerate();
+        loop {
+            match cursor.next() {
+                Some((_, (_, c))) if self.delimiters.contains(c) => {
+                    if in_word {
+                        words.push(core::mem::take(&mut acc));
+                        in_word = false;
+                    }
+                },
+                Some((_, (_, c))) if !in_word && self.comments && c == '#' => {
+                    for (_, (_, c)) in cursor.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                },
+                Some((next_idx, (next_pos, c))) if c == '\'' => {
+                    in_word = true;
+                    if !unquote_open_single(&mut acc, &mut cursor) {
+                        return Err(
+                            UnquoteError::UnterminatedSingleQuote {
+                                char_cursor: next_idx,
+                                byte_cursor: next_pos,
+                            }
+                        );
+                    }
+                },
+                Some((next_idx, (next_pos, c))) if c == '"' => {
+                    in_word = true;
+                    if !unquote_open_double(&mut acc, &mut cursor) {
+                        return Err(
+                            UnquoteError::UnterminatedDoubleQuote {
+                                char_cursor: next_idx,
+                                byte_cursor: next_pos,
+                            }
+                        );
+                    }
+                },
+                Some((_, (_, c))) if c == '\\' => {
+                    in_word = true;
+                    unquote_open_escape(&mut acc, &mut cursor);
+                },
+                Some((_, (_, c))) => {
+                    in_word = true;
+                    acc.push(c);
+                },
+                None => {
+                    if in_word {
+                        words.push(acc);
+                    }
+                    break Ok(words);
+                },
+            }
+        }
+    }
+}
+
+/// Split a command line into its arguments
+///
+/// This tokenizes a full command-line string into its individual arguments, following the same
+/// POSIX Shell quoting and escaping rules as [`unquote()`]. Arguments are separated by runs of
+/// unquoted whitespace (space, tab, or newline), which are collapsed, so leading, trailing, and
+/// repeated whitespace produce no empty arguments. However, an empty quoted sequence (e.g. `''`)
+/// does start a new argument, so `''` splits into a single empty string.
+///
+/// If the input is not well-formed (e.g. an unterminated quote), this fails the same way
+/// [`unquote()`] does, including the diagnostic cursor information.
+///
+/// This is equivalent to `SplitOptions::new().split(source)`. Use [`SplitOptions`] directly to
+/// customize delimiters or enable `#`-comment handling.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(
+///     r_shquote::split("foo 'bar baz'  qux").unwrap(),
+///     vec!["foo", "bar baz", "qux"],
+/// );
+/// ```
+pub fn split(source: &str) -> Result<Vec<String>, UnquoteError> {
+    SplitOptions::new().split(source)
+}
+
+/// Join arguments into a command line
+///
+/// This takes a list of arguments and joins them into a single command-line string, quoting each
+/// argument with [`quote()`] and separating them with a single space. The [`split()`] operation
+/// implements the inverse.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(r_shquote::join(vec!["foo", "bar baz"]), "'foo' 'bar baz'");
+/// ```
+pub fn join<'a, I: IntoIterator<Item = &'a str>>(args: I) -> String {
+    let mut acc = String::new();
+
+    for (i, arg) in args.into_iter().enumerate() {
+        if i > 0 {
+            acc.push(' ');
+        }
+        acc.push_str(&quote(arg));
+    }
+
+    acc
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
     #[test]
     fn basic() {
         assert_eq!(quote("foobar"), "'foobar'");
@@ -285,4 +669,78 @@ mod tests {
         assert_eq!(unquote("\\foobar\\").unwrap(), "foobar");
         assert_eq!(unquote("\\'foobar\\'").unwrap(), "'foobar'");
     }
+
+    #[test]
+    fn split_join() {
+        assert_eq!(split("").unwrap(), Vec::<String>::new());
+        assert_eq!(split("   ").unwrap(), Vec::<String>::new());
+        assert_eq!(split("foobar").unwrap(), vec!["foobar"]);
+        assert_eq!(split("  foo  bar  ").unwrap(), vec!["foo", "bar"]);
+        assert_eq!(split("foo 'bar baz' qux").unwrap(), vec!["foo", "bar baz", "qux"]);
+        assert_eq!(split("''").unwrap(), vec![""]);
+        assert_eq!(split("foo\\ bar").unwrap(), vec!["foo bar"]);
+
+        assert!(split("'foo").is_err());
+
+        assert_eq!(join(vec!["foo", "bar baz"]), "'foo' 'bar baz'");
+        assert_eq!(split(&join(vec!["foo", "bar baz", ""])).unwrap(), vec!["foo", "bar baz", ""]);
+    }
+
+    #[test]
+    fn split_options() {
+        // Comments are not recognized by default.
+        assert_eq!(split("foo # bar").unwrap(), vec!["foo", "#", "bar"]);
+
+        let opts = SplitOptions::new().comments(true);
+        assert_eq!(opts.split("foo # bar\nbaz").unwrap(), vec!["foo", "baz"]);
+        assert_eq!(opts.split("# a whole comment line").unwrap(), Vec::<String>::new());
+        // A `#` in the middle of a word is still literal.
+        assert_eq!(opts.split("foo#bar").unwrap(), vec!["foo#bar"]);
+
+        let opts = SplitOptions::new().delimiters(",");
+        assert_eq!(opts.split("foo,bar,baz").unwrap(), vec!["foo", "bar", "baz"]);
+    }
+
+    #[test]
+    fn try_quote_rejects_control_characters() {
+        assert_eq!(try_quote("foobar").unwrap(), "'foobar'");
+
+        assert!(matches!(
+            try_quote("foo\x1bbar").unwrap_err(),
+            QuoteError::ControlCharacter { char_cursor: 3, byte_cursor: 3 },
+        ));
+        assert!(matches!(
+            try_quote("foo\nbar").unwrap_err(),
+            QuoteError::ControlCharacter { char_cursor: 3, byte_cursor: 3 },
+        ));
+        assert!(try_quote("foo\x07bar").is_err());
+        assert!(try_quote("foo\x7fbar").is_err());
+    }
+
+    #[test]
+    fn ansi_c_quoting() {
+        assert_eq!(quote_ansi_c("foobar"), "'foobar'");
+        assert_eq!(quote_ansi_c("foo\nbar"), "$'foo\\nbar'");
+        assert_eq!(quote_ansi_c("foo\tbar"), "$'foo\\tbar'");
+        assert_eq!(quote_ansi_c("foo\\bar"), "'foo\\bar'");
+        assert_eq!(quote_ansi_c("foo\x1bbar"), "$'foo\\x1bbar'");
+        assert_eq!(quote_ansi_c("foo'\nbar"), "$'foo\\'\\nbar'");
+
+        // U+0085 (NEL) is a C1 control character encoded as two UTF-8 bytes (`\xc2\x85`); each
+        // byte must get its own `\xHH` escape so the output round-trips to the original
+        // character rather than a single, truncated byte.
+        assert_eq!(quote_ansi_c("foo\u{0085}bar"), "$'foo\\xc2\\x85bar'");
+    }
+
+    #[test]
+    fn cow_variants() {
+        assert!(matches!(quote_cow("foobar"), Cow::Borrowed("foobar")));
+        assert!(matches!(quote_cow(""), Cow::Owned(_)));
+        assert!(matches!(quote_cow("foo bar"), Cow::Owned(_)));
+        assert_eq!(quote_cow("foo bar"), quote("foo bar"));
+
+        assert!(matches!(unquote_cow("foobar").unwrap(), Cow::Borrowed("foobar")));
+        assert!(matches!(unquote_cow("'foobar'").unwrap(), Cow::Owned(_)));
+        assert_eq!(unquote_cow("'foobar'").unwrap(), unquote("'foobar'").unwrap());
+    }
 }